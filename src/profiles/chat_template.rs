@@ -0,0 +1,142 @@
+use super::tokenizer::TokenizerSource;
+use anyhow::Context;
+use minijinja::{context, Environment};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// One turn of a chat conversation, in the role-tagged shape chat templates
+/// expect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// A chat template pulled from a model's `tokenizer_config.json` or
+/// supplied directly by the user, ready to render a list of messages into
+/// the single prompt string the endpoint sees. Carries `bos_token`/
+/// `eos_token` alongside the template source since most real-world
+/// templates (Llama, Mistral, Gemma, ...) reference them.
+#[derive(Debug, Clone)]
+pub struct ChatTemplate {
+    source: String,
+    bos_token: String,
+    eos_token: String,
+}
+
+impl ChatTemplate {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            bos_token: String::new(),
+            eos_token: String::new(),
+        }
+    }
+
+    /// Resolve the chat template to use for a profile: `explicit` (inline
+    /// Jinja source, or a path to a file containing it) if given, otherwise
+    /// the target model's own `tokenizer_config.json`.
+    pub fn resolve(explicit: Option<&str>, tokenizer_source: Option<&TokenizerSource>) -> anyhow::Result<Self> {
+        if let Some(explicit) = explicit {
+            if Path::new(explicit).is_file() {
+                let source = std::fs::read_to_string(explicit)
+                    .with_context(|| format!("failed to read chat template file {explicit}"))?;
+                return Ok(Self::new(source));
+            }
+            return Ok(Self::new(explicit.to_string()));
+        }
+        let tokenizer_source = tokenizer_source
+            .ok_or_else(|| anyhow::anyhow!("chat template requires either an explicit chat_template or a tokenizer"))?;
+        let config_path = super::tokenizer::fetch_tokenizer_config(tokenizer_source)?;
+        Self::from_tokenizer_config(&config_path)
+    }
+
+    /// Load a chat template from a `tokenizer_config.json` file, reading
+    /// its `chat_template`, `bos_token` and `eos_token` fields.
+    pub fn from_tokenizer_config(path: &std::path::Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read tokenizer config {}", path.display()))?;
+        let config: serde_json::Value = serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse tokenizer config {}", path.display()))?;
+        let template = config
+            .get("chat_template")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("{} has no chat_template field", path.display()))?;
+        Ok(Self {
+            source: template.to_string(),
+            bos_token: special_token(&config, "bos_token"),
+            eos_token: special_token(&config, "eos_token"),
+        })
+    }
+
+    /// Render `messages` through the template, producing the prompt string
+    /// that should be tokenized for length accounting.
+    pub fn render(&self, messages: &[ChatMessage]) -> anyhow::Result<String> {
+        let mut env = Environment::new();
+        env.add_template("chat", &self.source)
+            .context("failed to parse chat template")?;
+        let tmpl = env.get_template("chat").context("chat template not found")?;
+        tmpl.render(context! {
+            messages => messages,
+            add_generation_prompt => true,
+            bos_token => self.bos_token,
+            eos_token => self.eos_token,
+        })
+        .context("failed to render chat template")
+    }
+}
+
+/// `tokenizer_config.json` represents special tokens either as a bare
+/// string or as an object with a `content` field; accept either, falling
+/// back to an empty string when the model's config doesn't define one.
+fn special_token(config: &serde_json::Value, key: &str) -> String {
+    match config.get(key) {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Object(obj)) => obj
+            .get("content")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_binds_bos_and_eos_tokens_into_the_template() {
+        let mut template = ChatTemplate::new("{{ bos_token }}{% for m in messages %}{{ m.content }}{% endfor %}{{ eos_token }}");
+        template.bos_token = "<s>".to_string();
+        template.eos_token = "</s>".to_string();
+        let messages = [ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        }];
+
+        let rendered = template.render(&messages).unwrap();
+
+        assert_eq!(rendered, "<s>hi</s>");
+    }
+
+    #[test]
+    fn from_tokenizer_config_reads_chat_template_and_special_tokens() {
+        let config = serde_json::json!({
+            "chat_template": "{{ bos_token }}{{ messages[0].content }}",
+            "bos_token": "<s>",
+            "eos_token": {"content": "</s>"},
+        });
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, config.to_string().as_bytes()).unwrap();
+
+        let template = ChatTemplate::from_tokenizer_config(file.path()).unwrap();
+        let messages = [ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        }];
+
+        assert_eq!(template.render(&messages).unwrap(), "<s>hi");
+        assert_eq!(template.eos_token, "</s>");
+    }
+}
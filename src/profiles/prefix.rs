@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use tokenizers::Tokenizer;
+
+/// Controls how prompts are built for the "shared-prefix" profile: a fixed
+/// common prefix shared by a fraction of requests, concatenated with a
+/// short, unique suffix, to exercise a server's prompt/prefix cache.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrefixOptions {
+    /// Length, in tokens, of the shared prefix.
+    pub prefix_tokens: u32,
+    /// Length, in tokens, of the unique suffix appended after the prefix.
+    pub suffix_tokens: u32,
+    /// Fraction (0.0-1.0) of requests that reuse the same prefix, rather
+    /// than drawing a fresh one that won't hit the server's cache.
+    pub prefix_share_ratio: f32,
+}
+
+/// A fixed prefix, computed once, reused verbatim by `prefix_share_ratio`
+/// of requests; the rest get their own fresh prefix. Every request also
+/// gets a unique suffix, so the server always sees a distinct prompt.
+pub struct PrefixPromptBuilder {
+    options: PrefixOptions,
+    shared_prefix: String,
+}
+
+impl PrefixPromptBuilder {
+    pub fn new(options: PrefixOptions, tokenizer: &Tokenizer) -> anyhow::Result<Self> {
+        let shared_prefix = filler_text(tokenizer, options.prefix_tokens, 0)?;
+        Ok(Self { options, shared_prefix })
+    }
+
+    pub fn build_prompt(&self, request_index: u64, tokenizer: &Tokenizer) -> anyhow::Result<String> {
+        let reuses_shared_prefix = (request_index % 1000) < (self.options.prefix_share_ratio * 1000.0) as u64;
+        // Distinct seeds for the fresh prefix and the suffix: `filler_text`
+        // is deterministic per seed, so sharing one would make the suffix a
+        // leading substring of the prefix instead of independent content.
+        let prefix = if reuses_shared_prefix {
+            self.shared_prefix.clone()
+        } else {
+            filler_text(tokenizer, self.options.prefix_tokens, request_index * 2 + 1)?
+        };
+        let suffix = filler_text(tokenizer, self.options.suffix_tokens, request_index * 2 + 2)?;
+        Ok(format!("{prefix} {suffix}"))
+    }
+}
+
+/// Deterministically build `num_tokens` of filler text by repeating a
+/// sentence that opens with `seed` and truncating to the exact token count.
+fn filler_text(tokenizer: &Tokenizer, num_tokens: u32, seed: u64) -> anyhow::Result<String> {
+    if num_tokens == 0 {
+        return Ok(String::new());
+    }
+    let sentence = format!("Request {seed}: the quick brown fox jumps over the lazy dog. ");
+    let mut text = String::new();
+    while tokenizer
+        .encode(text.as_str(), false)
+        .map_err(|e| anyhow::anyhow!("failed to tokenize filler text: {e}"))?
+        .len() < num_tokens as usize
+    {
+        text.push_str(&sentence);
+    }
+    let encoding = tokenizer
+        .encode(text.as_str(), false)
+        .map_err(|e| anyhow::anyhow!("failed to tokenize filler text: {e}"))?;
+    tokenizer
+        .decode(&encoding.get_ids()[..num_tokens as usize], true)
+        .map_err(|e| anyhow::anyhow!("failed to decode filler text: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tokenizers::models::wordlevel::WordLevel;
+    use tokenizers::pre_tokenizers::whitespace::Whitespace;
+
+    fn test_tokenizer() -> Tokenizer {
+        let mut vocab = HashMap::new();
+        vocab.insert("[UNK]".to_string(), 0);
+        // Numbers are the only known words, so the seed in each filler
+        // sentence decodes as itself and everything else as [UNK].
+        for i in 1..2000u32 {
+            vocab.insert(i.to_string(), i);
+        }
+        let model = WordLevel::builder()
+            .vocab(vocab)
+            .unk_token("[UNK]".to_string())
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+        tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+        tokenizer
+    }
+
+    #[test]
+    fn requests_below_the_share_ratio_reuse_the_shared_prefix() {
+        let tokenizer = test_tokenizer();
+        let options = PrefixOptions {
+            prefix_tokens: 6,
+            suffix_tokens: 4,
+            prefix_share_ratio: 0.5,
+        };
+        let builder = PrefixPromptBuilder::new(options, &tokenizer).unwrap();
+
+        let reused = builder.build_prompt(400, &tokenizer).unwrap();
+        let fresh = builder.build_prompt(600, &tokenizer).unwrap();
+
+        assert!(reused.starts_with(&builder.shared_prefix));
+        assert!(!fresh.starts_with(&builder.shared_prefix));
+    }
+
+    #[test]
+    fn fresh_prefix_and_suffix_use_different_seeds() {
+        let tokenizer = test_tokenizer();
+        let options = PrefixOptions {
+            prefix_tokens: 6,
+            suffix_tokens: 6,
+            prefix_share_ratio: 0.0,
+        };
+        let builder = PrefixPromptBuilder::new(options, &tokenizer).unwrap();
+
+        let prompt = builder.build_prompt(3, &tokenizer).unwrap();
+        let expected_prefix = filler_text(&tokenizer, 6, 7).unwrap();
+        let expected_suffix = filler_text(&tokenizer, 6, 8).unwrap();
+
+        assert_ne!(expected_prefix, expected_suffix);
+        assert_eq!(prompt, format!("{expected_prefix} {expected_suffix}"));
+    }
+}
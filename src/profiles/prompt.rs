@@ -0,0 +1,181 @@
+use super::chat_template::{ChatMessage, ChatTemplate};
+use super::prefix::PrefixPromptBuilder;
+use super::tokenizer::{fit_to_target, load_tokenizer, TokenizerSource};
+use crate::RunConfiguration;
+use serde_json::{json, Value};
+use tokenizers::Tokenizer;
+
+/// Everything a profile needs to build prompts for a run, resolved once
+/// up front and reused for every request: re-resolving the chat template
+/// (a Hub fetch) or reloading the tokenizer on every request would both be
+/// slow and pollute the latency metrics the benchmark is trying to measure.
+pub struct PromptContext {
+    tokenizer: Option<Tokenizer>,
+    chat_template: Option<ChatTemplate>,
+    prefix_builder: Option<PrefixPromptBuilder>,
+}
+
+impl PromptContext {
+    /// Resolve everything `build_prompt` will need from `run_configuration`
+    /// once per run.
+    pub fn prepare(run_configuration: &RunConfiguration) -> anyhow::Result<Self> {
+        let tokenizer_source = run_configuration.tokenizer.as_deref().map(TokenizerSource::parse);
+        let tokenizer = tokenizer_source.as_ref().map(load_tokenizer).transpose()?;
+        let chat_template = if run_configuration.apply_chat_template {
+            Some(ChatTemplate::resolve(
+                run_configuration.chat_template.as_deref(),
+                tokenizer_source.as_ref(),
+            )?)
+        } else {
+            None
+        };
+        let prefix_builder = match (&run_configuration.prefix_options, &tokenizer) {
+            (Some(prefix_options), Some(tokenizer)) => {
+                Some(PrefixPromptBuilder::new(prefix_options.clone(), tokenizer)?)
+            }
+            (Some(_), None) => {
+                anyhow::bail!("the shared-prefix profile requires a tokenizer to size its prefix")
+            }
+            (None, _) => None,
+        };
+        Ok(Self {
+            tokenizer,
+            chat_template,
+            prefix_builder,
+        })
+    }
+
+    /// Build the prompt text for one benchmark request out of its dataset
+    /// turns (or, for the "shared-prefix" profile, out of generated filler
+    /// text instead).
+    pub fn build_prompt(
+        &self,
+        run_configuration: &RunConfiguration,
+        turns: &[ChatMessage],
+        request_index: u64,
+    ) -> anyhow::Result<String> {
+        if let Some(builder) = &self.prefix_builder {
+            // `prepare` already required a tokenizer to build `builder`.
+            let tokenizer = self.tokenizer.as_ref().expect("prefix_builder implies tokenizer");
+            return builder.build_prompt(request_index, tokenizer);
+        }
+
+        if let Some(template) = &self.chat_template {
+            return template.render(turns);
+        }
+
+        let joined = turns
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        match (&self.tokenizer, &run_configuration.prompt_options) {
+            (Some(tokenizer), Some(options)) => fit_to_target(tokenizer, &joined, options),
+            _ => Ok(joined),
+        }
+    }
+
+    /// Build both the prompt and its request payload for one benchmark
+    /// request.
+    pub fn prepare_request(
+        &self,
+        run_configuration: &RunConfiguration,
+        turns: &[ChatMessage],
+        request_index: u64,
+    ) -> anyhow::Result<(String, Value)> {
+        let prompt = self.build_prompt(run_configuration, turns, request_index)?;
+        let payload = build_request_payload(run_configuration, &prompt);
+        Ok((prompt, payload))
+    }
+}
+
+/// Build the JSON request body sent to the inference endpoint for one
+/// request: the prompt plus whatever sampling parameters the profile set.
+pub fn build_request_payload(run_configuration: &RunConfiguration, prompt: &str) -> Value {
+    let mut payload = json!({ "inputs": prompt });
+    if let Some(sampling) = &run_configuration.sampling {
+        sampling.merge_into(&mut payload);
+    }
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::prefix::PrefixOptions;
+    use super::super::sampling::SamplingOptions;
+    use super::*;
+
+    fn base_run_configuration() -> RunConfiguration {
+        RunConfiguration {
+            max_vus: 1,
+            duration: std::time::Duration::from_secs(1),
+            rates: None,
+            num_rates: 1,
+            benchmark_kind: "sweep".to_string(),
+            warmup_duration: std::time::Duration::from_secs(1),
+            prompt_options: None,
+            decode_options: None,
+            dataset: "base-dataset".to_string(),
+            dataset_file: "base.json".to_string(),
+            chat_template: None,
+            apply_chat_template: false,
+            tokenizer: None,
+            sampling: None,
+            prefix_options: None,
+        }
+    }
+
+    #[test]
+    fn build_prompt_joins_turns_when_nothing_else_is_configured() {
+        let run_configuration = base_run_configuration();
+        let context = PromptContext::prepare(&run_configuration).unwrap();
+        let turns = [
+            ChatMessage {
+                role: "user".to_string(),
+                content: "hi".to_string(),
+            },
+            ChatMessage {
+                role: "assistant".to_string(),
+                content: "hello".to_string(),
+            },
+        ];
+
+        let prompt = context.build_prompt(&run_configuration, &turns, 0).unwrap();
+
+        assert_eq!(prompt, "hi\nhello");
+    }
+
+    #[test]
+    fn prepare_request_forwards_sampling_parameters() {
+        let mut run_configuration = base_run_configuration();
+        run_configuration.sampling = Some(SamplingOptions {
+            temperature: Some(0.5),
+            ..Default::default()
+        });
+        let context = PromptContext::prepare(&run_configuration).unwrap();
+        let turns = [ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        }];
+
+        let (prompt, payload) = context.prepare_request(&run_configuration, &turns, 0).unwrap();
+
+        assert_eq!(prompt, "hi");
+        assert_eq!(payload["parameters"]["temperature"], json!(0.5));
+    }
+
+    #[test]
+    fn prepare_requires_a_tokenizer_when_prefix_options_are_set() {
+        let mut run_configuration = base_run_configuration();
+        run_configuration.prefix_options = Some(PrefixOptions {
+            prefix_tokens: 4,
+            suffix_tokens: 4,
+            prefix_share_ratio: 0.5,
+        });
+
+        let result = PromptContext::prepare(&run_configuration);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use super::prefix::PrefixOptions;
+use super::sampling::SamplingOptions;
+use crate::{RunConfiguration, TokenizeOptions};
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+const BUILTIN_FIXED_LENGTH: &str = include_str!("builtin/fixed-length.toml");
+const BUILTIN_CHAT: &str = include_str!("builtin/chat.toml");
+const BUILTIN_CODE_GENERATION: &str = include_str!("builtin/code-generation.toml");
+const BUILTIN_SHARED_PREFIX: &str = include_str!("builtin/shared-prefix.toml");
+
+/// A named, partial `RunConfiguration` loaded from TOML. Every field is
+/// optional so a profile file only has to specify what it wants to
+/// override; anything left out falls back to the configuration passed in
+/// on the command line.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProfileDefinition {
+    pub max_vus: Option<u64>,
+    pub duration_secs: Option<u64>,
+    /// An empty list clears a caller-supplied `--rates`; omitted leaves it untouched.
+    pub rates: Option<Vec<f64>>,
+    pub num_rates: Option<usize>,
+    pub benchmark_kind: Option<String>,
+    pub warmup_duration_secs: Option<u64>,
+    pub prompt_options: Option<TokenizeOptions>,
+    pub decode_options: Option<TokenizeOptions>,
+    pub dataset: Option<String>,
+    pub dataset_file: Option<String>,
+    /// Inline Jinja source, or a path to a file containing it. Falls back
+    /// to the target model's `tokenizer_config.json` when unset.
+    pub chat_template: Option<String>,
+    pub apply_chat_template: Option<bool>,
+    /// A `tokenizer.json` path, or a hub repo id.
+    pub tokenizer: Option<String>,
+    pub sampling: Option<SamplingOptions>,
+    pub prefix_options: Option<PrefixOptions>,
+}
+
+impl ProfileDefinition {
+    /// Overlay this definition on top of `run_configuration`, keeping
+    /// whatever the caller already set for fields this profile doesn't
+    /// mention.
+    pub fn apply(&self, run_configuration: RunConfiguration) -> RunConfiguration {
+        RunConfiguration {
+            max_vus: self.max_vus.unwrap_or(run_configuration.max_vus),
+            duration: self
+                .duration_secs
+                .map(Duration::from_secs)
+                .unwrap_or(run_configuration.duration),
+            rates: match &self.rates {
+                Some(rates) if rates.is_empty() => None,
+                Some(rates) => Some(rates.clone()),
+                None => run_configuration.rates,
+            },
+            num_rates: self.num_rates.unwrap_or(run_configuration.num_rates),
+            benchmark_kind: self
+                .benchmark_kind
+                .clone()
+                .unwrap_or(run_configuration.benchmark_kind),
+            warmup_duration: self
+                .warmup_duration_secs
+                .map(Duration::from_secs)
+                .unwrap_or(run_configuration.warmup_duration),
+            prompt_options: self.prompt_options.clone().or(run_configuration.prompt_options),
+            decode_options: self.decode_options.clone().or(run_configuration.decode_options),
+            dataset: self.dataset.clone().unwrap_or(run_configuration.dataset),
+            dataset_file: self.dataset_file.clone().unwrap_or(run_configuration.dataset_file),
+            chat_template: self.chat_template.clone().or(run_configuration.chat_template),
+            apply_chat_template: self
+                .apply_chat_template
+                .unwrap_or(run_configuration.apply_chat_template),
+            tokenizer: self.tokenizer.clone().or(run_configuration.tokenizer),
+            sampling: self.sampling.clone().or(run_configuration.sampling),
+            prefix_options: self.prefix_options.clone().or(run_configuration.prefix_options),
+            ..run_configuration
+        }
+    }
+
+    /// Overlay `other` on top of `self`, field by field, keeping `self`'s
+    /// value wherever `other` leaves a field unset. Used to let a
+    /// user-supplied profile file tweak a handful of fields on a built-in
+    /// profile without dropping the rest of its defaults. Nested tables
+    /// (`sampling`, `prompt_options`, `decode_options`, `prefix_options`)
+    /// are replaced whole, not merged key by key.
+    fn merge(&mut self, other: Self) {
+        if other.max_vus.is_some() {
+            self.max_vus = other.max_vus;
+        }
+        if other.duration_secs.is_some() {
+            self.duration_secs = other.duration_secs;
+        }
+        if other.rates.is_some() {
+            self.rates = other.rates;
+        }
+        if other.num_rates.is_some() {
+            self.num_rates = other.num_rates;
+        }
+        if other.benchmark_kind.is_some() {
+            self.benchmark_kind = other.benchmark_kind;
+        }
+        if other.warmup_duration_secs.is_some() {
+            self.warmup_duration_secs = other.warmup_duration_secs;
+        }
+        if other.prompt_options.is_some() {
+            self.prompt_options = other.prompt_options;
+        }
+        if other.decode_options.is_some() {
+            self.decode_options = other.decode_options;
+        }
+        if other.dataset.is_some() {
+            self.dataset = other.dataset;
+        }
+        if other.dataset_file.is_some() {
+            self.dataset_file = other.dataset_file;
+        }
+        if other.chat_template.is_some() {
+            self.chat_template = other.chat_template;
+        }
+        if other.apply_chat_template.is_some() {
+            self.apply_chat_template = other.apply_chat_template;
+        }
+        if other.tokenizer.is_some() {
+            self.tokenizer = other.tokenizer;
+        }
+        if other.sampling.is_some() {
+            self.sampling = other.sampling;
+        }
+        if other.prefix_options.is_some() {
+            self.prefix_options = other.prefix_options;
+        }
+    }
+}
+
+/// The built-in profiles, keyed by name, in the same format a user-supplied
+/// profile file uses.
+fn builtin_registry() -> anyhow::Result<HashMap<String, ProfileDefinition>> {
+    let mut registry = HashMap::new();
+    registry.insert("fixed-length".to_string(), toml::from_str(BUILTIN_FIXED_LENGTH)?);
+    registry.insert("chat".to_string(), toml::from_str(BUILTIN_CHAT)?);
+    registry.insert(
+        "code-generation".to_string(),
+        toml::from_str(BUILTIN_CODE_GENERATION)?,
+    );
+    registry.insert("shared-prefix".to_string(), toml::from_str(BUILTIN_SHARED_PREFIX)?);
+    Ok(registry)
+}
+
+/// Build the full profile registry: built-ins overlaid with whatever is
+/// declared in `profile_file`, if any. A profile file may redefine a
+/// built-in name to tweak it, or introduce new names entirely.
+pub fn load_registry(profile_file: Option<&Path>) -> anyhow::Result<HashMap<String, ProfileDefinition>> {
+    let mut registry = builtin_registry()?;
+    if let Some(path) = profile_file {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read profile file {}", path.display()))?;
+        let custom: HashMap<String, ProfileDefinition> = toml::from_str(&contents)
+            .with_context(|| format!("failed to parse profile file {}", path.display()))?;
+        for (name, overrides) in custom {
+            match registry.get_mut(&name) {
+                Some(existing) => existing.merge(overrides),
+                None => {
+                    registry.insert(name, overrides);
+                }
+            }
+        }
+    }
+    Ok(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_overlays_only_the_fields_the_profile_sets() {
+        let base = RunConfiguration {
+            max_vus: 1,
+            duration: Duration::from_secs(1),
+            rates: None,
+            num_rates: 1,
+            benchmark_kind: "sweep".to_string(),
+            warmup_duration: Duration::from_secs(1),
+            prompt_options: None,
+            decode_options: None,
+            dataset: "base-dataset".to_string(),
+            dataset_file: "base.json".to_string(),
+            chat_template: None,
+            apply_chat_template: false,
+            tokenizer: None,
+            sampling: None,
+            prefix_options: None,
+        };
+        let definition = ProfileDefinition {
+            max_vus: Some(64),
+            dataset: Some("override-dataset".to_string()),
+            ..Default::default()
+        };
+
+        let applied = definition.apply(base);
+
+        assert_eq!(applied.max_vus, 64);
+        assert_eq!(applied.dataset, "override-dataset");
+        // Untouched fields fall back to what the caller already had.
+        assert_eq!(applied.num_rates, 1);
+        assert_eq!(applied.dataset_file, "base.json");
+        // A profile that doesn't mention `rates` leaves the caller's value alone.
+        assert_eq!(applied.rates, None);
+    }
+
+    #[test]
+    fn empty_rates_in_a_profile_clears_a_caller_supplied_rates() {
+        let base = RunConfiguration {
+            max_vus: 1,
+            duration: Duration::from_secs(1),
+            rates: Some(vec![1.0, 2.0]),
+            num_rates: 1,
+            benchmark_kind: "sweep".to_string(),
+            warmup_duration: Duration::from_secs(1),
+            prompt_options: None,
+            decode_options: None,
+            dataset: "base-dataset".to_string(),
+            dataset_file: "base.json".to_string(),
+            chat_template: None,
+            apply_chat_template: false,
+            tokenizer: None,
+            sampling: None,
+            prefix_options: None,
+        };
+        let definition = ProfileDefinition {
+            rates: Some(vec![]),
+            ..Default::default()
+        };
+
+        let applied = definition.apply(base);
+
+        assert_eq!(applied.rates, None);
+    }
+
+    #[test]
+    fn custom_profile_file_merges_onto_builtin_instead_of_replacing_it() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"[chat.sampling]\ntemperature = 1.0\n").unwrap();
+
+        let registry = load_registry(Some(file.path())).unwrap();
+
+        let merged = registry.get("chat").unwrap();
+        assert_eq!(merged.sampling.as_ref().unwrap().temperature, Some(1.0));
+        // A profile file overriding one field must not drop the builtin's
+        // other defaults.
+        assert_eq!(merged.dataset.as_deref(), Some("hlarcher/inference-benchmarker"));
+        assert_eq!(merged.dataset_file.as_deref(), Some("share_gpt_turns.json"));
+        assert_eq!(merged.max_vus, Some(128));
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Sampling/generation parameters forwarded to the inference endpoint's
+/// request payload, controlling how the server generates rather than how
+/// many tokens a profile asks for.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SamplingOptions {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub top_k: Option<u32>,
+    pub repetition_penalty: Option<f64>,
+    pub max_tokens: Option<u32>,
+    pub min_new_tokens: Option<u32>,
+}
+
+impl SamplingOptions {
+    /// Merge the parameters that are set into a `parameters` object on
+    /// `payload`, the request body sent to the inference endpoint.
+    pub fn merge_into(&self, payload: &mut Value) {
+        let mut parameters = Map::new();
+        if let Some(temperature) = self.temperature {
+            parameters.insert("temperature".to_string(), json!(temperature));
+        }
+        if let Some(top_p) = self.top_p {
+            parameters.insert("top_p".to_string(), json!(top_p));
+        }
+        if let Some(top_k) = self.top_k {
+            parameters.insert("top_k".to_string(), json!(top_k));
+        }
+        if let Some(repetition_penalty) = self.repetition_penalty {
+            parameters.insert("repetition_penalty".to_string(), json!(repetition_penalty));
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            parameters.insert("max_new_tokens".to_string(), json!(max_tokens));
+        }
+        if let Some(min_new_tokens) = self.min_new_tokens {
+            parameters.insert("min_new_tokens".to_string(), json!(min_new_tokens));
+        }
+        if parameters.is_empty() {
+            return;
+        }
+        payload
+            .as_object_mut()
+            .expect("request payload must be a JSON object")
+            .insert("parameters".to_string(), Value::Object(parameters));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_into_only_adds_the_parameters_that_are_set() {
+        let options = SamplingOptions {
+            temperature: Some(0.2),
+            max_tokens: Some(64),
+            ..Default::default()
+        };
+        let mut payload = json!({ "inputs": "hello" });
+
+        options.merge_into(&mut payload);
+
+        assert_eq!(payload["parameters"]["temperature"], json!(0.2));
+        assert_eq!(payload["parameters"]["max_new_tokens"], json!(64));
+        assert!(payload["parameters"].get("top_p").is_none());
+    }
+
+    #[test]
+    fn merge_into_leaves_payload_untouched_when_nothing_is_set() {
+        let mut payload = json!({ "inputs": "hello" });
+
+        SamplingOptions::default().merge_into(&mut payload);
+
+        assert!(payload.get("parameters").is_none());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::TokenizeOptions;
+use anyhow::Context;
+use std::path::{Path, PathBuf};
+use tokenizers::Tokenizer;
+
+/// Where to load a profile's tokenizer from: a local `tokenizer.json`, or a
+/// HuggingFace Hub repo id to fetch it from.
+#[derive(Debug, Clone)]
+pub enum TokenizerSource {
+    File(String),
+    HubRepo(String),
+}
+
+impl TokenizerSource {
+    /// A bare string is a hub repo id unless it looks like a path to a
+    /// `tokenizer.json` file on disk.
+    pub fn parse(source: &str) -> Self {
+        if Path::new(source).is_file() {
+            Self::File(source.to_string())
+        } else {
+            Self::HubRepo(source.to_string())
+        }
+    }
+}
+
+/// Load the tokenizer a profile's length targeting should be measured
+/// against. This parses a `tokenizer.json` (or fetches one from the Hub),
+/// so callers should load it once per run and reuse it rather than calling
+/// this per request.
+pub fn load_tokenizer(source: &TokenizerSource) -> anyhow::Result<Tokenizer> {
+    match source {
+        TokenizerSource::File(path) => {
+            Tokenizer::from_file(path).map_err(|e| anyhow::anyhow!("failed to load tokenizer {}: {}", path, e))
+        }
+        TokenizerSource::HubRepo(repo_id) => {
+            let api = hf_hub::api::sync::Api::new().context("failed to create HuggingFace Hub API client")?;
+            let tokenizer_path = api
+                .model(repo_id.clone())
+                .get("tokenizer.json")
+                .with_context(|| format!("failed to fetch tokenizer.json for {repo_id}"))?;
+            Tokenizer::from_file(&tokenizer_path)
+                .map_err(|e| anyhow::anyhow!("failed to load tokenizer for {}: {}", repo_id, e))
+        }
+    }
+}
+
+/// Trim `text` down to the token-count target in `options`, measured with
+/// `tokenizer`.
+pub fn fit_to_target(tokenizer: &Tokenizer, text: &str, options: &TokenizeOptions) -> anyhow::Result<String> {
+    let target = options.num_tokens.unwrap_or(options.max_tokens);
+    let encoding = tokenizer
+        .encode(text, false)
+        .map_err(|e| anyhow::anyhow!("failed to tokenize prompt: {e}"))?;
+    let ids = encoding.get_ids();
+    if ids.len() as u32 <= target {
+        return Ok(text.to_string());
+    }
+    tokenizer
+        .decode(&ids[..target as usize], true)
+        .map_err(|e| anyhow::anyhow!("failed to decode truncated prompt: {e}"))
+}
+
+/// Resolve the `tokenizer_config.json` that sits alongside a profile's
+/// tokenizer, fetching it from the Hub for a repo id or looking next to a
+/// local `tokenizer.json` file.
+pub(super) fn fetch_tokenizer_config(source: &TokenizerSource) -> anyhow::Result<PathBuf> {
+    match source {
+        TokenizerSource::File(path) => {
+            let config_path = Path::new(path).with_file_name("tokenizer_config.json");
+            if !config_path.is_file() {
+                anyhow::bail!("no tokenizer_config.json next to tokenizer file {path}");
+            }
+            Ok(config_path)
+        }
+        TokenizerSource::HubRepo(repo_id) => {
+            let api = hf_hub::api::sync::Api::new().context("failed to create HuggingFace Hub API client")?;
+            api.model(repo_id.clone())
+                .get("tokenizer_config.json")
+                .with_context(|| format!("failed to fetch tokenizer_config.json for {repo_id}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_treats_an_existing_path_as_a_file_source() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let source = TokenizerSource::parse(file.path().to_str().unwrap());
+        assert!(matches!(source, TokenizerSource::File(_)));
+    }
+
+    #[test]
+    fn parse_treats_anything_else_as_a_hub_repo_id() {
+        let source = TokenizerSource::parse("bigcode/starcoder");
+        assert!(matches!(source, TokenizerSource::HubRepo(repo) if repo == "bigcode/starcoder"));
+    }
+}